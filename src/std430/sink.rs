@@ -0,0 +1,97 @@
+use std::io::{self, Seek, SeekFrom, Write};
+
+/// Largest alignment that can occur in `std430` layout, used to size the
+/// scratch buffer that [`StreamSink`] zero-fills padding from.
+const ZEROS: [u8; 16] = [0; 16];
+
+/// Destination that [`Writer`][super::Writer] writes bytes and padding into.
+///
+/// This exists so `Writer` can write efficiently to an in-memory buffer
+/// (the common case of a mapped GPU buffer) without paying the overhead of
+/// `std::io::Write`, while still supporting arbitrary writers like files or
+/// sockets.
+pub(crate) trait Sink {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()>;
+    fn write_zeros(&mut self, len: usize) -> io::Result<()>;
+}
+
+/// A [`Sink`] backed by a `&mut [u8]`, giving one bounds check and one
+/// `copy_from_slice`/`fill` per call instead of one `write_all` call per byte
+/// of padding. A value and its padding are two calls, each checked
+/// separately.
+pub(crate) struct SliceSink<'a> {
+    slice: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> SliceSink<'a> {
+    pub(crate) fn new(slice: &'a mut [u8]) -> Self {
+        Self { slice, position: 0 }
+    }
+}
+
+impl<'a> Sink for SliceSink<'a> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let target = self.reserve(bytes.len())?;
+        target.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn write_zeros(&mut self, len: usize) -> io::Result<()> {
+        let target = self.reserve(len)?;
+        target.fill(0);
+        Ok(())
+    }
+}
+
+impl<'a> SliceSink<'a> {
+    fn reserve(&mut self, len: usize) -> io::Result<&mut [u8]> {
+        let end = self.position.checked_add(len).ok_or_else(too_small)?;
+        let target = self
+            .slice
+            .get_mut(self.position..end)
+            .ok_or_else(too_small)?;
+        self.position = end;
+        Ok(target)
+    }
+}
+
+/// A [`Sink`] that writes to any [`std::io::Write`] stream, batching padding
+/// into as few `write_all` calls as possible.
+pub(crate) struct StreamSink<'a, W> {
+    writer: &'a mut W,
+}
+
+impl<'a, W: Write> StreamSink<'a, W> {
+    pub(crate) fn new(writer: &'a mut W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<'a, W: Write> Sink for StreamSink<'a, W> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)
+    }
+
+    fn write_zeros(&mut self, mut len: usize) -> io::Result<()> {
+        while len > 0 {
+            let chunk = len.min(ZEROS.len());
+            self.writer.write_all(&ZEROS[..chunk])?;
+            len -= chunk;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, W: Write + Seek> StreamSink<'a, W> {
+    /// Seeks the underlying stream to an absolute byte offset.
+    pub(crate) fn seek(&mut self, offset: u64) -> io::Result<()> {
+        self.writer.seek(SeekFrom::Start(offset))?;
+        Ok(())
+    }
+}
+
+fn too_small() -> io::Error {
+    io::Error::new(io::ErrorKind::WriteZero, "slice is not large enough")
+}
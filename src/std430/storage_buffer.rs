@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+use crate::std430::{AsStd430, Std430, Writer};
+
+/**
+Helper for writing a `std430` storage buffer made up of a fixed "body"
+struct immediately followed by a dynamically sized array, such as:
+
+```glsl
+struct PointLight {
+    vec3 position;
+    vec3 color;
+    float brightness;
+};
+
+buffer POINT_LIGHTS {
+    uint len;
+    PointLight[] lights;
+} point_lights;
+```
+
+`StorageBuffer` takes care of inserting the padding required between the
+body and the array so that the array starts at the correct alignment, even
+when the body's alignment is stricter than the array element's. It returns
+the total number of bytes written so that callers can size or allocate
+their GPU buffer ahead of time.
+
+## Example
+```
+use crevice::std430::{self, AsStd430};
+
+#[derive(AsStd430)]
+struct PointLightsHeader {
+    len: u32,
+}
+
+#[derive(AsStd430)]
+struct PointLight {
+    position: mint::Vector3<f32>,
+    color: mint::Vector3<f32>,
+    brightness: f32,
+}
+
+let lights = vec![
+    PointLight {
+        position: [0.0, 1.0, 0.0].into(),
+        color: [1.0, 0.0, 0.0].into(),
+        brightness: 0.6,
+    },
+    PointLight {
+        position: [0.0, 4.0, 3.0].into(),
+        color: [1.0, 1.0, 1.0].into(),
+        brightness: 1.0,
+    },
+];
+
+let header = PointLightsHeader {
+    len: lights.len() as u32,
+};
+
+let mut target_buffer = Vec::new();
+let size = std430::StorageBuffer::write(&header, &lights, &mut target_buffer)?;
+
+// 4-byte header, padded 12 bytes to the 16-byte alignment of PointLight,
+// plus 2 32-byte PointLights.
+assert_eq!(size, 80);
+
+# Ok::<(), std::io::Error>(())
+```
+*/
+pub struct StorageBuffer<T, U> {
+    _body: PhantomData<T>,
+    _items: PhantomData<U>,
+}
+
+impl<T, U> StorageBuffer<T, U>
+where
+    T: AsStd430,
+    U: AsStd430,
+{
+    /// Writes `body` followed by `items` to `out`, inserting the padding
+    /// required for `items` to start at the correct alignment.
+    ///
+    /// Returns the total number of bytes written.
+    pub fn write(body: &T, items: &[U], out: &mut impl Write) -> io::Result<usize> {
+        let mut writer = Writer::new(out);
+
+        writer.write(body)?;
+
+        let array_align = T::Output::ALIGNMENT.max(U::Output::ALIGNMENT);
+        writer.pad(array_align)?;
+
+        writer.write(items)?;
+
+        Ok(writer.len())
+    }
+}
@@ -1,9 +1,11 @@
-use std::io::{self, Write};
+use std::io::{self, Seek, Write};
+use std::marker::PhantomData;
 use std::mem::size_of;
 
 use bytemuck::bytes_of;
 
 use crate::internal::align_offset;
+use crate::std430::sink::{Sink, SliceSink, StreamSink};
 use crate::std430::{AsStd430, Std430, WriteStd430};
 
 /**
@@ -13,6 +15,11 @@ Type that enables writing correctly aligned `std430` values to a buffer.
 represented by a struct alone, like dynamically sized arrays or dynamically
 laid-out values.
 
+Construct a `Writer` with [`Writer::from_slice`] when the destination is
+already an in-memory buffer, such as a mapped GPU buffer; this is the
+fastest option. Use [`Writer::new`] to write into any [`std::io::Write`]
+stream, such as a file.
+
 ## Example
 In this example, we'll write a length-prefixed list of lights to a buffer.
 `std430::Writer` helps align correctly, even across multiple structs, which can
@@ -58,7 +65,7 @@ let lights = vec![
 #     Box::leak(vec![0; 1024].into_boxed_slice())
 # }
 let target_buffer = map_gpu_buffer_for_write();
-let mut writer = std430::Writer::new(target_buffer);
+let mut writer = std430::Writer::from_slice(target_buffer);
 
 let light_count = lights.len() as u32;
 writer.write(&light_count)?;
@@ -75,18 +82,91 @@ unmap_gpu_buffer();
 # Ok::<(), std::io::Error>(())
 ```
 */
-pub struct Writer<'a, W> {
-    writer: &'a mut W,
+pub struct Writer<S> {
+    sink: S,
     offset: usize,
+    dynamic_align: usize,
+}
+
+fn assert_valid_dynamic_align(align: usize) -> usize {
+    assert_ne!(align, 0, "dynamic alignment must not be zero");
+    align
 }
 
-impl<'a, W: Write> Writer<'a, W> {
+impl<'a, W: Write> Writer<StreamSink<'a, W>> {
     /// Create a new `Writer`, wrapping a buffer, file, or other type that
     /// implements [`std::io::Write`].
     pub fn new(writer: &'a mut W) -> Self {
-        Self { writer, offset: 0 }
+        Self {
+            sink: StreamSink::new(writer),
+            offset: 0,
+            dynamic_align: 1,
+        }
+    }
+
+    /// Create a new `Writer` whose [`write_dynamic`][Writer::write_dynamic]
+    /// entry point rounds each value's start offset up to a multiple of
+    /// `align`.
+    ///
+    /// This is useful for packing multiple independent `std430` blocks into
+    /// one buffer that will be bound with dynamic offsets, which typically
+    /// must be a multiple of the device's minimum storage/uniform buffer
+    /// offset alignment (commonly 256). `write`/`write_std430` are
+    /// unaffected and continue to align only to each value's natural
+    /// `std430` alignment.
+    ///
+    /// `align` must be nonzero, and must be a multiple of the natural
+    /// `std430` alignment of every type that will be passed to
+    /// `write_dynamic`, or the offset returned by `write_dynamic` is not
+    /// guaranteed to actually be a multiple of `align`. Panics if `align` is
+    /// zero.
+    pub fn with_dynamic_alignment(writer: &'a mut W, align: usize) -> Self {
+        Self {
+            sink: StreamSink::new(writer),
+            offset: 0,
+            dynamic_align: assert_valid_dynamic_align(align),
+        }
+    }
+}
+
+impl<'a> Writer<SliceSink<'a>> {
+    /// Create a new `Writer` backed directly by a `&mut [u8]`, such as a
+    /// mapped GPU buffer.
+    ///
+    /// This is faster than [`Writer::new`] because it skips `io::Write`,
+    /// writing each value and its padding directly into the slice, with one
+    /// bounds check and one `copy_from_slice`/`fill` for the padding and
+    /// another for the value.
+    pub fn from_slice(slice: &'a mut [u8]) -> Self {
+        Self {
+            sink: SliceSink::new(slice),
+            offset: 0,
+            dynamic_align: 1,
+        }
+    }
+
+    /// Create a new `Writer` backed directly by a `&mut [u8]` whose
+    /// [`write_dynamic`][Writer::write_dynamic] entry point rounds each
+    /// value's start offset up to a multiple of `align`.
+    ///
+    /// See [`Writer::with_dynamic_alignment`] for the stream-backed
+    /// equivalent; this is the same thing for a mapped GPU buffer.
+    ///
+    /// `align` must be nonzero, and must be a multiple of the natural
+    /// `std430` alignment of every type that will be passed to
+    /// `write_dynamic`, or the offset returned by `write_dynamic` is not
+    /// guaranteed to actually be a multiple of `align`. Panics if `align` is
+    /// zero.
+    pub fn from_slice_with_dynamic_alignment(slice: &'a mut [u8], align: usize) -> Self {
+        Self {
+            sink: SliceSink::new(slice),
+            offset: 0,
+            dynamic_align: assert_valid_dynamic_align(align),
+        }
     }
+}
 
+impl<S: Sink> Writer<S> {
     /// Write a new value to the underlying buffer, writing zeroed padding where
     /// necessary.
     ///
@@ -128,14 +208,11 @@ impl<'a, W: Write> Writer<'a, W> {
         T: Std430,
     {
         let padding = align_offset(self.offset, T::ALIGNMENT);
-
-        for _ in 0..padding {
-            self.writer.write_all(&[0])?;
-        }
+        self.sink.write_zeros(padding)?;
         self.offset += padding;
 
         let value = value.as_std430();
-        self.writer.write_all(bytes_of(&value))?;
+        self.sink.write(bytes_of(&value))?;
 
         let write_here = self.offset;
         self.offset += size_of::<T>();
@@ -147,4 +224,142 @@ impl<'a, W: Write> Writer<'a, W> {
     pub fn len(&self) -> usize {
         self.offset
     }
+
+    /// Write a new value, rounding its start offset up to the dynamic
+    /// alignment configured with
+    /// [`Writer::with_dynamic_alignment`][Writer::with_dynamic_alignment] or
+    /// [`Writer::from_slice_with_dynamic_alignment`]
+    /// instead of the type's natural `std430` alignment.
+    ///
+    /// Returns the offset the value was written to, for use as the dynamic
+    /// offset when binding this block on its own.
+    ///
+    /// ## Example
+    /// ```
+    /// use crevice::std430::{self, AsStd430};
+    ///
+    /// #[derive(AsStd430)]
+    /// struct Block {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut buffer = vec![0u8; 1024];
+    /// let mut writer = std430::Writer::from_slice_with_dynamic_alignment(&mut buffer, 256);
+    ///
+    /// let offset_a = writer.write_dynamic(&Block { value: 1 })?;
+    /// let offset_b = writer.write_dynamic(&Block { value: 2 })?;
+    ///
+    /// assert_eq!(offset_a, 0);
+    /// assert_eq!(offset_b, 256);
+    ///
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn write_dynamic<T>(&mut self, value: &T) -> io::Result<usize>
+    where
+        T: WriteStd430,
+    {
+        self.pad(self.dynamic_align)?;
+        value.write_std430(self)
+    }
+
+    /// Writes zeroed padding until `self.len()` is a multiple of `align`.
+    ///
+    /// This is a lower-level primitive than [`write`][Writer::write], useful
+    /// when a caller needs to align to something other than a single type's
+    /// natural `std430` alignment, such as the start of an array following a
+    /// differently-aligned struct.
+    pub(crate) fn pad(&mut self, align: usize) -> io::Result<()> {
+        let padding = align_offset(self.offset, align);
+        self.sink.write_zeros(padding)?;
+        self.offset += padding;
+
+        Ok(())
+    }
+}
+
+/// A reserved, zeroed slot previously written by
+/// [`Writer::write_placeholder`], to be filled in later with
+/// [`Writer::patch`] once its value becomes known.
+///
+/// ## Example
+/// Here we don't know the number of items up front, so we reserve a
+/// placeholder for the length prefix, stream the items, then patch the
+/// count back in once it's known.
+/// ```
+/// use std::io::Cursor;
+/// use crevice::std430::{self, AsStd430};
+///
+/// #[derive(AsStd430)]
+/// struct Item {
+///     value: u32,
+/// }
+///
+/// let mut buffer = Cursor::new(Vec::new());
+/// let mut writer = std430::Writer::new(&mut buffer);
+///
+/// let len_placeholder = writer.write_placeholder::<u32>()?;
+///
+/// let items = vec![Item { value: 1 }, Item { value: 2 }, Item { value: 3 }];
+/// let len = items.len() as u32;
+/// writer.write_iter(items)?;
+///
+/// writer.patch(len_placeholder, &len)?;
+///
+/// let bytes = buffer.into_inner();
+/// assert_eq!(bytes.len(), 16);
+/// assert_eq!(&bytes[0..4], &3u32.to_ne_bytes());
+///
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Placeholder<T> {
+    offset: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, W: Write + Seek> Writer<StreamSink<'a, W>> {
+    /// Reserves space for a value that isn't known yet, writing zeroed bytes
+    /// in its place and returning a [`Placeholder`] that can be filled in
+    /// later with [`Writer::patch`].
+    ///
+    /// This is useful for a length prefix or offset table that depends on
+    /// data written after it, such as a `uint len` preceding a list streamed
+    /// with [`write_iter`][Writer::write_iter]: reserve the placeholder,
+    /// stream the list while counting its items, then patch in the count.
+    pub fn write_placeholder<T>(&mut self) -> io::Result<Placeholder<T>>
+    where
+        T: Std430,
+    {
+        self.pad(T::ALIGNMENT)?;
+
+        let offset = self.offset;
+        self.sink.write_zeros(size_of::<T>())?;
+        self.offset += size_of::<T>();
+
+        Ok(Placeholder {
+            offset,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Fills in a [`Placeholder`] previously returned by
+    /// [`Writer::write_placeholder`] with `value`.
+    ///
+    /// Seeks back to the placeholder's reserved offset, overwrites it, then
+    /// seeks forward again so that subsequent writes continue from where
+    /// they left off.
+    pub fn patch<T>(&mut self, placeholder: Placeholder<T>, value: &T) -> io::Result<()>
+    where
+        T: Std430,
+    {
+        let value = value.as_std430();
+        let bytes = bytes_of(&value);
+
+        let end = self.offset;
+
+        self.sink.seek(placeholder.offset as u64)?;
+        self.sink.write(bytes)?;
+        self.sink.seek(end as u64)?;
+
+        Ok(())
+    }
 }